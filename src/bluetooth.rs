@@ -3,29 +3,31 @@ use std::time::Duration;
 use btleplug::api::{BDAddr, Central, Manager as _, Peripheral as _, ScanFilter};
 use btleplug::platform::{Adapter, Manager, Peripheral};
 use tokio::time;
+use uuid::Uuid;
 
+use crate::desk::UUID_ADV_SVC;
 use crate::error::DeskError;
 
 const RETRY_COUNT: usize = 3;
 
-/// Locate the first adapter on the device. If the device does not support
-/// or have access to bluetooth then this will fail.
+/// Locate the adapter at `adapter_index` on the device. If the device does not support or have
+/// access to bluetooth, or there is no adapter at that index, then this will fail.
 ///
 /// # Arguments
 ///
 /// * `manager`: The bluetooth device manager.
+/// * `adapter_index`: Which adapter to use, for machines with more than one bluetooth
+///   controller. Index 0 is the first adapter reported by the OS.
 ///
-/// returns: Option<Adapter>
+/// returns: Result<Adapter, DeskError>
 ///
-async fn find_first_adapter(manager: &Manager) -> Option<Adapter> {
-    let central_adapter = manager
+async fn find_first_adapter(manager: &Manager, adapter_index: usize) -> Result<Adapter, DeskError> {
+    manager
         .adapters()
-        .await
-        .expect("Unable to fetch adapter list.")
+        .await?
         .into_iter()
-        .nth(0);
-
-    central_adapter
+        .nth(adapter_index)
+        .ok_or(DeskError::NoAdapterFound)
 }
 
 /// Locate the desk by the given desk_address.  
@@ -56,6 +58,7 @@ async fn find_desk(
 /// # Arguments
 ///
 /// * `manager`: The manager used to locate the desk.
+/// * `adapter_index`: Which adapter to scan and connect with, see `find_first_adapter`.
 /// * `connect`: If we should try to connect or not.
 ///
 /// returns: Result<Peripheral, DeskError>
@@ -63,17 +66,21 @@ async fn find_desk(
 pub(crate) async fn find_desk_adapter(
     address: BDAddr,
     manager: &Manager,
+    adapter_index: usize,
     connect: bool,
 ) -> Result<Peripheral, DeskError> {
-    let adapter = find_first_adapter(&manager).await.unwrap();
+    let adapter = find_first_adapter(&manager, adapter_index).await?;
 
     // start scanning for devices, this could probably be something related to polling
     // instead of this method of scanning. Something that could be started earlier.
     // This is a little slow and could be faster.
-    adapter.start_scan(ScanFilter::default()).await.unwrap();
+    adapter
+        .start_scan(ScanFilter::default())
+        .await
+        .map_err(|_| DeskError::ScanFailed)?;
     time::sleep(Duration::from_secs(3)).await;
 
-    let desk_peripheral = find_desk(address, &adapter).await?.unwrap();
+    let desk_peripheral = find_desk(address, &adapter).await?.ok_or(DeskError::DeskNotFound)?;
 
     if !connect {
         return Ok(desk_peripheral);
@@ -85,7 +92,7 @@ pub(crate) async fn find_desk_adapter(
         match desk_peripheral.connect().await {
             Ok(_) => break,
             Err(e) => {
-                if i == RETRY_COUNT {
+                if i == RETRY_COUNT - 1 {
                     return Err(e.into());
                 }
 
@@ -100,3 +107,45 @@ pub(crate) async fn find_desk_adapter(
 
     Ok(desk_peripheral)
 }
+
+/// Scan for peripherals advertising the desk's service uuid (`UUID_ADV_SVC`), for first-time
+/// setup when the desk's MAC address isn't already known.
+///
+/// # Arguments
+///
+/// * `manager`: The manager used to locate candidate peripherals.
+/// * `adapter_index`: Which adapter to scan with, see `find_first_adapter`.
+///
+/// returns: Result<Vec<(String, BDAddr)>, DeskError>
+///
+pub(crate) async fn discover_desks(
+    manager: &Manager,
+    adapter_index: usize,
+) -> Result<Vec<(String, BDAddr)>, DeskError> {
+    let adapter = find_first_adapter(&manager, adapter_index).await?;
+
+    adapter
+        .start_scan(ScanFilter::default())
+        .await
+        .map_err(|_| DeskError::ScanFailed)?;
+    time::sleep(Duration::from_secs(3)).await;
+
+    let adv_service_uuid = Uuid::parse_str(UUID_ADV_SVC).expect("UUID_ADV_SVC is a valid uuid");
+    let mut desks = Vec::new();
+
+    for peripheral in adapter.peripherals().await? {
+        let Some(properties) = peripheral.properties().await? else {
+            continue;
+        };
+
+        if properties.services.contains(&adv_service_uuid) {
+            let name = properties
+                .local_name
+                .unwrap_or_else(|| "unknown".to_string());
+
+            desks.push((name, properties.address));
+        }
+    }
+
+    Ok(desks)
+}
@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::DeskError;
+
+/// Fallback sit height, used when no `"sit"` preset has been saved.
+pub const DEFAULT_SIT_HEIGHT: f32 = 0.74;
+
+/// Fallback stand height, used when no `"stand"` preset has been saved.
+pub const DEFAULT_STAND_HEIGHT: f32 = 1.12;
+
+/// Persisted user configuration: the desk's MAC address and a set of named height presets
+/// (e.g. `sit`, `stand`, `meeting`), stored as TOML under the standard config directory.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Config {
+    pub mac_address: Option<String>,
+
+    #[serde(default)]
+    pub presets: HashMap<String, f32>,
+}
+
+impl Config {
+    /// Load the config from the standard config directory, returning an empty config if one
+    /// hasn't been saved yet.
+    ///
+    /// returns: Result<Config, DeskError>
+    ///
+    pub fn load() -> Result<Config, DeskError> {
+        let path = config_path()?;
+
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+
+        let contents = fs::read_to_string(&path)?;
+
+        Ok(toml::from_str(&contents)?)
+    }
+
+    /// Persist the config to the standard config directory, creating it if required.
+    ///
+    /// returns: Result<(), DeskError>
+    ///
+    pub fn save(&self) -> Result<(), DeskError> {
+        let path = config_path()?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(&path, toml::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+}
+
+/// The path to the config file, under the platform's standard config directory, e.g.
+/// `~/.config/idasen-desk-lib/config.toml` on Linux.
+///
+/// returns: Result<PathBuf, DeskError>
+///
+fn config_path() -> Result<PathBuf, DeskError> {
+    let config_dir = dirs::config_dir().ok_or(DeskError::ConfigDirNotFound)?;
+
+    Ok(config_dir.join("idasen-desk-lib").join("config.toml"))
+}
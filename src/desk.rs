@@ -1,11 +1,12 @@
 use std::collections::{BTreeSet, HashMap};
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 use std::time::Duration;
 
 use btleplug::api::{BDAddr, Characteristic, Peripheral as _, PeripheralProperties, WriteType};
 use btleplug::platform::{Manager, Peripheral};
 use futures::StreamExt;
-use tokio::sync::RwLock;
+use tokio::sync::{watch, Mutex, RwLock};
+use tokio::task::JoinHandle;
 use tokio::time::Instant;
 
 use crate::{bluetooth, DeskError};
@@ -14,13 +15,22 @@ static UUID_HEIGHT: &str = "99fa0021-338a-1024-8a49-009c0215f78a";
 static UUID_COMMAND: &str = "99fa0002-338a-1024-8a49-009c0215f78a";
 static UUID_REFERENCE_INPUT: &str = "99fa0031-338a-1024-8a49-009c0215f78a";
 
-// Not currently used but can be used to determine if the given device is a desk or not. If it is
-// a desk then the services (services_uuid) list will contain this uuid.
-#[allow(dead_code)]
-static UUID_ADV_SVC: &str = "99fa0001-338a-1024-8a49-009c0215f78a";
+// Used to determine if a peripheral is a desk or not, via its advertised services - if it is a
+// desk then the advertised services (`services`) list will contain this uuid.
+pub(crate) static UUID_ADV_SVC: &str = "99fa0001-338a-1024-8a49-009c0215f78a";
 
-static MAX_HEIGHT: f32 = 1.27;
-static MIN_HEIGHT: f32 = 0.62;
+pub(crate) static MAX_HEIGHT: f32 = 1.27;
+pub(crate) static MIN_HEIGHT: f32 = 0.62;
+
+// Calibrated approximate linear speed of the desk column while moving, in meters/sec. Used to
+// dead-reckon the current height between BLE notifications, which otherwise only arrive roughly
+// every 300-500ms and leave the 50ms control loop working against a stale reading.
+static DESK_SPEED: f32 = 0.03;
+
+// If no real height notification has arrived in this long, the desk may have stopped, dropped a
+// notification, or something else unexpected has happened - discard the dead-reckoned estimate
+// and fall back to a fresh `get_height()` read rather than keep driving blind.
+static MAX_EST_INTERVAL: Duration = Duration::from_secs(1);
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Direction {
@@ -34,16 +44,24 @@ pub struct Desk {
     desk_properties: PeripheralProperties,
     desk_characteristics: BTreeSet<Characteristic>,
     characteristics_map: HashMap<String, Characteristic>,
+    mac_address: BDAddr,
+    adapter_index: usize,
+    height_tx: watch::Sender<f32>,
+    notification_task: Mutex<JoinHandle<()>>,
 }
 
 impl Desk {
-    pub async fn new(mac_address: &str) -> Result<Desk, DeskError> {
-        let manager = Manager::new().await.unwrap();
+    pub async fn new(mac_address: &str, adapter_index: usize) -> Result<Desk, DeskError> {
+        let manager = Manager::new().await?;
+
+        let address = mac_address
+            .parse::<BDAddr>()
+            .map_err(|_| DeskError::DeskNotFound)?;
 
-        let address = mac_address.parse::<BDAddr>().unwrap();
-        let desk_peripheral = bluetooth::find_desk_adapter(address, &manager, true).await?;
+        let desk_peripheral =
+            bluetooth::find_desk_adapter(address, &manager, adapter_index, true).await?;
 
-        Ok(Desk::from_peripheral(desk_peripheral).await?)
+        Desk::from_peripheral(desk_peripheral, address, adapter_index).await
     }
 
     /// Create a new instance of the desk from a bluetooth peripheral.
@@ -51,44 +69,117 @@ impl Desk {
     /// # Arguments
     ///
     /// * `peripheral`: The desk Peripheral for communicating over bluetooth.
+    /// * `mac_address`: The peripheral's MAC address, kept so the desk can be relocated and
+    ///   reconnected to if the connection drops.
+    /// * `adapter_index`: The bluetooth adapter the peripheral was found on, reused on reconnect.
     ///
-    /// returns: Desk
+    /// returns: Result<Desk, DeskError>
     ///
-    pub async fn from_peripheral(peripheral: Peripheral) -> Result<Desk, DeskError> {
-        let desk_properties = peripheral.properties().await.unwrap().unwrap();
+    pub async fn from_peripheral(
+        peripheral: Peripheral,
+        mac_address: BDAddr,
+        adapter_index: usize,
+    ) -> Result<Desk, DeskError> {
+        let desk_properties = peripheral.properties().await?.ok_or(DeskError::DeskNotFound)?;
         let desk_characteristics = peripheral.characteristics();
 
-        let name = desk_properties.local_name.as_ref().unwrap();
+        let name = desk_properties
+            .local_name
+            .as_ref()
+            .ok_or(DeskError::DeskNotFound)?;
+
         let desk_characteristics_map = get_character_map(&desk_characteristics);
 
-        if peripheral
-            .subscribe(desk_characteristics_map.get(UUID_HEIGHT).unwrap())
-            .await
-            .is_err()
-        {
+        let height_characteristic = desk_characteristics_map
+            .get(UUID_HEIGHT)
+            .ok_or_else(|| DeskError::MissingCharacteristic(UUID_HEIGHT.to_string()))?;
+
+        if peripheral.subscribe(height_characteristic).await.is_err() {
             return Err(DeskError::CannotSubscribePosition);
         }
 
+        let initial_height = bytes_to_meters(peripheral.read(height_characteristic).await?);
+
         log::debug!("created new instance of device {:?}", name);
 
+        let peripheral = Arc::new(RwLock::new(peripheral));
+        let (height_tx, _) = watch::channel(initial_height);
+        let notification_task = spawn_height_forwarder(peripheral.clone(), height_tx.clone());
+
         let desk = Desk {
             name: name.to_string(),
             desk_properties,
-            peripheral: Arc::new(RwLock::new(peripheral)),
+            peripheral,
             desk_characteristics,
             characteristics_map: desk_characteristics_map,
+            mac_address,
+            adapter_index,
+            height_tx,
+            notification_task: Mutex::new(notification_task),
         };
 
-        // desk.read_height_notifications().await;
-
         Ok(desk)
     }
 
-    /// Get the current height of the desk by communicating over bluetooth
+    /// Re-scan for the desk and reconnect, replacing the current (presumably dropped)
+    /// connection. BLE desks frequently drop idle links, so callers fall back to this rather
+    /// than erroring out on a lost connection.
     ///
-    /// returns: <Result<f32, btleplug::Error>
-    pub async fn get_height(&self) -> Result<f32, btleplug::Error> {
-        let characteristic = self.characteristics_map.get(UUID_HEIGHT).unwrap();
+    /// Notification subscriptions (CCCDs) are per-connection, so the height characteristic is
+    /// re-subscribed on the new peripheral and the background notification forwarder is
+    /// restarted against it - otherwise any live `subscribe_height()` receiver would silently
+    /// stop seeing real updates after the first reconnect.
+    ///
+    /// returns: Result<(), DeskError>
+    ///
+    async fn reconnect(&self) -> Result<(), DeskError> {
+        log::warn!("lost connection to desk {:?}, attempting to reconnect", self.name);
+
+        let manager = Manager::new().await?;
+        let peripheral =
+            bluetooth::find_desk_adapter(self.mac_address, &manager, self.adapter_index, true)
+                .await?;
+
+        let height_characteristic = self
+            .characteristics_map
+            .get(UUID_HEIGHT)
+            .ok_or_else(|| DeskError::MissingCharacteristic(UUID_HEIGHT.to_string()))?;
+
+        if peripheral.subscribe(height_characteristic).await.is_err() {
+            return Err(DeskError::CannotSubscribePosition);
+        }
+
+        *self.peripheral.write().await = peripheral;
+
+        let mut notification_task = self.notification_task.lock().await;
+        notification_task.abort();
+        *notification_task = spawn_height_forwarder(self.peripheral.clone(), self.height_tx.clone());
+
+        log::info!("reconnected to desk {:?}", self.name);
+
+        Ok(())
+    }
+
+    /// Get the current height of the desk by communicating over bluetooth. If the connection has
+    /// dropped, transparently reconnects and retries once rather than erroring out.
+    ///
+    /// returns: Result<f32, DeskError>
+    pub async fn get_height(&self) -> Result<f32, DeskError> {
+        match self.get_height_once().await {
+            Err(DeskError::BluetoothError(_)) => {
+                self.reconnect().await?;
+                self.get_height_once().await
+            }
+            result => result,
+        }
+    }
+
+    async fn get_height_once(&self) -> Result<f32, DeskError> {
+        let characteristic = self
+            .characteristics_map
+            .get(UUID_HEIGHT)
+            .ok_or_else(|| DeskError::MissingCharacteristic(UUID_HEIGHT.to_string()))?;
+
         let height_value = self.peripheral.read().await.read(characteristic).await?;
 
         Ok(bytes_to_meters(height_value))
@@ -97,13 +188,30 @@ impl Desk {
     /// Tell the desk to stop moving.
     ///
     /// The desk does not stop automatically unless the safety kicks in, otherwise move action
-    /// move the desk in steps of 1 second.
+    /// move the desk in steps of 1 second. If the connection has dropped, transparently
+    /// reconnects and retries once rather than erroring out.
     ///
-    /// returns: Result<(), Error>
+    /// returns: Result<(), DeskError>
     ///
-    pub async fn stop(&self) -> Result<(), btleplug::Error> {
-        let command_char = self.characteristics_map.get(UUID_COMMAND).unwrap();
-        let ref_char = self.characteristics_map.get(UUID_REFERENCE_INPUT).unwrap();
+    pub async fn stop(&self) -> Result<(), DeskError> {
+        match self.stop_once().await {
+            Err(DeskError::BluetoothError(_)) => {
+                self.reconnect().await?;
+                self.stop_once().await
+            }
+            result => result,
+        }
+    }
+
+    async fn stop_once(&self) -> Result<(), DeskError> {
+        let command_char = self
+            .characteristics_map
+            .get(UUID_COMMAND)
+            .ok_or_else(|| DeskError::MissingCharacteristic(UUID_COMMAND.to_string()))?;
+        let ref_char = self
+            .characteristics_map
+            .get(UUID_REFERENCE_INPUT)
+            .ok_or_else(|| DeskError::MissingCharacteristic(UUID_REFERENCE_INPUT.to_string()))?;
 
         let command_stop = vec![0xFF, 0x00];
         let command_ref_input = vec![0x01, 0x80];
@@ -112,11 +220,14 @@ impl Desk {
 
         // we call into both kinds since command char and ref char, linux
         // plays up if and when we use the normal method of calling.
-        let (_, _) = tokio::join!(
+        let (command_result, ref_result) = tokio::join!(
             per.write(command_char, &command_stop, WriteType::WithoutResponse),
             per.write(ref_char, &command_ref_input, WriteType::WithoutResponse)
         );
 
+        command_result?;
+        ref_result?;
+
         Ok(())
     }
 
@@ -154,20 +265,45 @@ impl Desk {
         let will_move_up = target > previous_height;
         log::info!("moving desk from {:?} to {:?}", previous_height, target);
 
-        // WIP
-        // WIP
-        // WIP
-        // TODO: update this so that its in another function and we pass in the desk height arch
-        // cloned which would allow it to be able to update the value. This function can then
-        // later be used as a --monitor method to monitor your desk height only
-        let desk_height = Arc::new(Mutex::new(previous_height.clone()));
+        let mut height_rx = self.subscribe_height().await;
 
-        let mut previous_height_read_at = Instant::now();
+        let mut last_notified_height = *height_rx.borrow();
+        let mut last_notified_at = Instant::now();
+        let mut previous_notified_height = last_notified_height;
 
-        let _ = self.monitor_height_notification_stream(desk_height.clone());
+        let mut previous_height_read_at = Instant::now();
 
         loop {
-            let current_height = *desk_height.lock().unwrap();
+            let notified_height = *height_rx.borrow();
+            let mut got_fresh_reading = false;
+
+            if notified_height != last_notified_height {
+                last_notified_height = notified_height;
+                last_notified_at = Instant::now();
+                got_fresh_reading = true;
+            }
+
+            let since_last_notification = last_notified_at.elapsed();
+
+            // between notifications, estimate the current height from the known direction of
+            // travel and the desk's calibrated speed instead of working against a stale reading.
+            // if we haven't heard from the desk in too long to trust that estimate, fall back to
+            // reading the height directly.
+            let current_height = if since_last_notification > MAX_EST_INTERVAL {
+                log::debug!(
+                    "no height notification in {since_last_notification:?}, discarding estimate and reading height directly"
+                );
+
+                let height = self.get_height().await?;
+                last_notified_height = height;
+                last_notified_at = Instant::now();
+                got_fresh_reading = true;
+                height
+            } else {
+                let direction = if will_move_up { 1.0 } else { -1.0 };
+                last_notified_height + direction * DESK_SPEED * since_last_notification.as_secs_f32()
+            };
+
             let elapsed_milliseconds = previous_height_read_at.elapsed().as_millis();
 
             let difference = target - current_height;
@@ -189,14 +325,25 @@ impl Desk {
             // something when moving. This will result in the desk moving in the opposite direction
             // when the device detects something. Moving out th way. If we detect this, stop.
             //
+            // this is only evaluated against real height notifications, not the dead-reckoning
+            // estimate above - the estimate can run ahead of a desk that moves slower than
+            // DESK_SPEED, which would otherwise trip this on a perfectly normal move once the
+            // next real notification reads in lower than expected.
+            //
             // only if our difference is not less than 10mm, meaning we are not doing a minor
             // correction, which might mean moving back up and down again.
-            if ((current_height < previous_height && will_move_up)
-                || current_height > previous_height && !will_move_up)
-                && difference_abs > 0.010
-            {
-                log::warn!("stopped moving because desk safety feature kicked in.");
-                return Err(super::DeskError::DeskMoveSafetyKickedIn);
+            if got_fresh_reading {
+                let notified_difference_abs = (last_notified_height - previous_notified_height).abs();
+
+                if ((last_notified_height < previous_notified_height && will_move_up)
+                    || last_notified_height > previous_notified_height && !will_move_up)
+                    && notified_difference_abs > 0.010
+                {
+                    log::warn!("stopped moving because desk safety feature kicked in.");
+                    return Err(super::DeskError::DeskMoveSafetyKickedIn);
+                }
+
+                previous_notified_height = last_notified_height;
             }
 
             // If we are either less than 10 millimetres, or less than half a second from target
@@ -215,8 +362,7 @@ impl Desk {
             if difference_abs <= 0.003 {
                 self.stop().await?;
 
-                let height = *desk_height.lock().unwrap();
-                log::info!("reached target of {target}, actual: {height}");
+                log::info!("reached target of {target}, actual: {current_height}");
 
                 return Ok(());
             }
@@ -227,7 +373,7 @@ impl Desk {
                 self.move_direction(Direction::Down).await?;
             }
 
-            previous_height = *desk_height.lock().unwrap();
+            previous_height = current_height;
             previous_height_read_at = Instant::now();
 
             // ensure to sleep a small amount, allowing the device becomes overwhelmed and results
@@ -254,7 +400,20 @@ impl Desk {
     ///
     /// ```
     pub async fn move_direction(&self, direction: Direction) -> Result<(), super::DeskError> {
-        let command_characteristic = self.characteristics_map.get(UUID_COMMAND).unwrap();
+        match self.move_direction_once(direction).await {
+            Err(DeskError::BluetoothError(_)) => {
+                self.reconnect().await?;
+                self.move_direction_once(direction).await
+            }
+            result => result,
+        }
+    }
+
+    async fn move_direction_once(&self, direction: Direction) -> Result<(), super::DeskError> {
+        let command_characteristic = self
+            .characteristics_map
+            .get(UUID_COMMAND)
+            .ok_or_else(|| DeskError::MissingCharacteristic(UUID_COMMAND.to_string()))?;
 
         let command = if direction == Direction::Up {
             vec![0x47, 0x00]
@@ -271,24 +430,19 @@ impl Desk {
         Ok(())
     }
 
-    async fn monitor_height_notification_stream(
-        &self,
-        height_reference: Arc<Mutex<f32>>,
-    ) -> Result<tokio::task::JoinHandle<()>, DeskError> {
-        let mut notifications_stream = self
-            .peripheral
-            .read()
-            .await
-            .notifications()
-            .await?
-            .take(1000);
-
-        Ok(tokio::spawn(async move {
-            while let Some(notification) = notifications_stream.next().await {
-                let notified_height = bytes_to_meters(notification.value.clone());
-                *height_reference.lock().unwrap() = notified_height;
-            }
-        }))
+    /// Subscribe to live height notifications from the desk, without initiating a move.
+    ///
+    /// The desk keeps a single long-lived notification forwarder running for its whole
+    /// lifetime (restarted by `reconnect` when a `get_height`, `stop` or `move_direction` call
+    /// finds the connection dropped; a drop is not noticed otherwise), so this just hands
+    /// out another receiver onto it rather than spawning a new one per call. Consumers can use
+    /// the returned receiver to react to height changes (logging, automations, UIs)
+    /// independently of `move_to_target`.
+    ///
+    /// returns: watch::Receiver<f32>
+    ///
+    pub async fn subscribe_height(&self) -> watch::Receiver<f32> {
+        self.height_tx.subscribe()
     }
 }
 
@@ -311,7 +465,39 @@ impl ToString for Desk {
     }
 }
 
-/// Debug log some basic properties when moving the desk.   
+/// Spawn a background task that forwards BLE height notifications onto `height_tx` as they
+/// arrive. One of these runs for the whole lifetime of a `Desk`; `reconnect` aborts and replaces
+/// it rather than letting it leak, since a peripheral's notification subscription doesn't carry
+/// over to a freshly reconnected one.
+///
+/// # Arguments
+///
+/// * `peripheral`: The peripheral to read notifications from.
+/// * `height_tx`: Where to forward decoded heights to.
+///
+/// returns: JoinHandle<()>
+///
+fn spawn_height_forwarder(
+    peripheral: Arc<RwLock<Peripheral>>,
+    height_tx: watch::Sender<f32>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut notifications_stream = match peripheral.read().await.notifications().await {
+            Ok(stream) => stream,
+            Err(e) => {
+                log::warn!("failed to read desk notifications: {e:?}");
+                return;
+            }
+        };
+
+        while let Some(notification) = notifications_stream.next().await {
+            let notified_height = bytes_to_meters(notification.value.clone());
+            height_tx.send_replace(notified_height);
+        }
+    })
+}
+
+/// Debug log some basic properties when moving the desk.
 ///
 fn log_basic_desk_information(
     target: f32,
@@ -13,4 +13,43 @@ pub enum DeskError {
 
     #[error("bluetooth error")]
     BluetoothError(#[from] btleplug::Error),
+
+    #[error("mqtt client error")]
+    MqttClientError(#[from] rumqttc::ClientError),
+
+    #[error("mqtt connection error")]
+    MqttConnectionError(#[from] rumqttc::ConnectionError),
+
+    #[error("could not locate a config directory on this platform")]
+    ConfigDirNotFound,
+
+    #[error("config io error")]
+    ConfigIoError(#[from] std::io::Error),
+
+    #[error("failed to parse config file")]
+    ConfigParseError(#[from] toml::de::Error),
+
+    #[error("failed to serialize config file")]
+    ConfigSerializeError(#[from] toml::ser::Error),
+
+    #[error("no preset named '{0}' has been saved")]
+    PresetNotFound(String),
+
+    #[error("no desk MAC address configured; pass --mac once to save it")]
+    MissingMacAddress,
+
+    #[error("no bluetooth adapter found")]
+    NoAdapterFound,
+
+    #[error("desk not found - make sure it's powered on and in range")]
+    DeskNotFound,
+
+    #[error("desk is missing expected characteristic '{0}'")]
+    MissingCharacteristic(String),
+
+    #[error("failed to start bluetooth scan")]
+    ScanFailed,
+
+    #[error("failed to subscribe to the desk's position characteristic")]
+    CannotSubscribePosition,
 }
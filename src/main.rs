@@ -5,11 +5,15 @@ use clap::Parser;
 use env_logger::Target;
 use log::LevelFilter;
 
+use crate::config::{Config, DEFAULT_SIT_HEIGHT, DEFAULT_STAND_HEIGHT};
 use crate::desk::Desk;
+use crate::error::DeskError;
 
 mod bluetooth;
+mod config;
 mod desk;
 mod error;
+mod mqtt;
 
 #[derive(Parser, Debug)]
 #[clap(about, version, author)]
@@ -17,6 +21,21 @@ struct Args {
     #[clap(long, short = 'v')]
     verbose: bool,
 
+    /// The desk's bluetooth MAC address. Only needs to be passed once; it is then saved to the
+    /// config file and reused on subsequent invocations.
+    #[clap(long)]
+    mac: Option<String>,
+
+    /// Which bluetooth adapter to use, for machines with more than one controller. Index 0 is
+    /// the first adapter reported by the OS.
+    #[clap(long, default_value_t = 0)]
+    hci: usize,
+
+    /// Scan for nearby desks and print their name and MAC address, then exit. Useful for
+    /// first-time setup when the desk's MAC address isn't already known.
+    #[clap(long)]
+    discover: bool,
+
     #[clap(long)]
     sit: bool,
 
@@ -25,6 +44,26 @@ struct Args {
 
     #[clap(long = "move", short = 'm')]
     move_to: Option<u8>,
+
+    #[clap(long)]
+    monitor: bool,
+
+    /// Move the desk to a previously saved named preset (see `--save-preset`).
+    #[clap(long)]
+    preset: Option<String>,
+
+    /// Save the desk's current height as a named preset, for later use with `--preset`.
+    #[clap(long)]
+    save_preset: Option<String>,
+
+    /// List all saved presets and exit.
+    #[clap(long)]
+    list_presets: bool,
+
+    /// Run as a long-lived MQTT service instead of a one-shot move, bridging the desk onto the
+    /// given broker (e.g. `localhost:1883`) with Home Assistant auto-discovery.
+    #[clap(long)]
+    mqtt_broker: Option<String>,
 }
 
 #[tokio::main]
@@ -46,12 +85,76 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     log::debug!("input arguments {:?}", cli_arguments);
 
-    let manager = Manager::new().await.unwrap();
-    let desk_peripheral = bluetooth::find_desk_adapter(&manager, true).await?;
+    let mut config = Config::load()?;
+
+    if cli_arguments.list_presets {
+        if config.presets.is_empty() {
+            println!("no presets saved yet, use --save-preset <name> to create one");
+        } else {
+            for (name, height) in &config.presets {
+                println!("{name}: {height:.3}m");
+            }
+        }
+
+        return Ok(());
+    }
+
+    if cli_arguments.discover {
+        let manager = Manager::new().await?;
+        let desks = bluetooth::discover_desks(&manager, cli_arguments.hci).await?;
+
+        if desks.is_empty() {
+            println!("no desks found, make sure it's powered and in range");
+        } else {
+            for (name, address) in desks {
+                println!("{name}: {address}");
+            }
+        }
+
+        return Ok(());
+    }
+
+    let mac_address = cli_arguments
+        .mac
+        .clone()
+        .or_else(|| config.mac_address.clone())
+        .ok_or(DeskError::MissingMacAddress)?;
+
+    if cli_arguments.mac.is_some() && config.mac_address.as_ref() != Some(&mac_address) {
+        config.mac_address = Some(mac_address.clone());
+        config.save()?;
+    }
 
-    let desk = Desk::new(desk_peripheral).await;
+    let desk = Desk::new(&mac_address, cli_arguments.hci).await?;
     log::info!("connected to desk: {:?}", desk.name);
 
+    if let Some(name) = cli_arguments.save_preset {
+        let height = desk.get_height().await?;
+        config.presets.insert(name.clone(), height);
+        config.save()?;
+
+        println!("saved preset '{name}' at {height:.3}m");
+        return Ok(());
+    }
+
+    if let Some(name) = cli_arguments.preset {
+        let target = config
+            .presets
+            .get(&name)
+            .copied()
+            .ok_or_else(|| DeskError::PresetNotFound(name))?;
+
+        desk.move_to_target(target).await?;
+        return Ok(());
+    }
+
+    // run as a persistent smart-desk controller, bridging the desk onto MQTT, instead of a
+    // one-shot move.
+    if let Some(broker) = cli_arguments.mqtt_broker {
+        mqtt::run(desk, &broker, &config).await?;
+        return Ok(());
+    }
+
     // handle the case in which the device target amount was specified. // we allow this being a
     // whole number, e.g 74, which will be later converted into a float value.
     if let Some(target_value) = cli_arguments.move_to {
@@ -59,26 +162,42 @@ async fn main() -> Result<(), Box<dyn Error>> {
         return Ok(());
     }
 
-    log::trace!("{}", desk.to_string());
+    // print the desk height every time a new notification comes in, without moving the desk,
+    // until the user interrupts the process (e.g. ctrl-c).
+    if cli_arguments.monitor {
+        let mut height_rx = desk.subscribe_height().await;
 
-    let current_desk_height = desk.get_height().await?;
-    log::debug!("starting desk position {:?}", current_desk_height);
+        loop {
+            height_rx.changed().await?;
+            println!("height: {:.3}m", *height_rx.borrow());
+        }
+    }
 
-    // if the user has specified sit or stand.
+    // if the user has specified sit or stand, using their saved preset if they have one.
     if cli_arguments.stand {
-        desk.move_to_target(1.12).await?;
+        let target = config
+            .presets
+            .get("stand")
+            .copied()
+            .unwrap_or(DEFAULT_STAND_HEIGHT);
+
+        desk.move_to_target(target).await?;
         return Ok(());
     } else if cli_arguments.sit {
-        desk.move_to_target(0.74).await?;
+        let target = config
+            .presets
+            .get("sit")
+            .copied()
+            .unwrap_or(DEFAULT_SIT_HEIGHT);
+
+        desk.move_to_target(target).await?;
         return Ok(());
     }
 
-    // otherwise lets go and determine it and do it ourself.
-    if current_desk_height > 1.0 {
-        desk.move_to_target(0.74).await?;
-    } else {
-        desk.move_to_target(1.12).await?;
-    }
+    log::trace!("{}", desk.to_string());
+
+    let current_desk_height = desk.get_height().await?;
+    log::info!("current desk height: {current_desk_height:.3}");
 
     Ok(())
 }
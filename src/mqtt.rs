@@ -0,0 +1,150 @@
+use std::time::Duration;
+
+use rumqttc::{AsyncClient, Event, MqttOptions, Packet, QoS};
+use serde_json::json;
+
+use crate::config::{Config, DEFAULT_SIT_HEIGHT, DEFAULT_STAND_HEIGHT};
+use crate::desk::{Desk, MAX_HEIGHT, MIN_HEIGHT};
+use crate::error::DeskError;
+
+const STATE_TOPIC: &str = "idasen_desk/state";
+const COMMAND_TOPIC: &str = "idasen_desk/set";
+const DISCOVERY_TOPIC: &str = "homeassistant/number/idasen_desk/config";
+
+/// Run the desk as a long-lived MQTT service.
+///
+/// Publishes the current height to `STATE_TOPIC` whenever it changes, and subscribes to
+/// `COMMAND_TOPIC` for target heights (e.g. `"0.85"`) or the `sit` / `stand` / `stop` shorthands,
+/// which consult `config`'s saved presets the same way `--sit`/`--stand` do before falling back
+/// to the default heights. A retained Home Assistant discovery config is published on startup so
+/// the desk appears automatically in Home Assistant.
+///
+/// # Arguments
+///
+/// * `desk`: The desk to bridge onto MQTT.
+/// * `broker`: The broker address, as `host:port` (e.g. `localhost:1883`).
+/// * `config`: The user's saved presets, consulted for the `sit`/`stand` shorthands.
+///
+/// returns: Result<(), DeskError>
+///
+pub async fn run(desk: Desk, broker: &str, config: &Config) -> Result<(), DeskError> {
+    let (host, port) = broker.rsplit_once(':').unwrap_or((broker, "1883"));
+    let port = port.parse::<u16>().unwrap_or(1883);
+
+    let mut mqtt_options = MqttOptions::new("idasen-desk-lib", host, port);
+    mqtt_options.set_keep_alive(Duration::from_secs(30));
+
+    let (client, mut event_loop) = AsyncClient::new(mqtt_options, 10);
+
+    client.subscribe(COMMAND_TOPIC, QoS::AtLeastOnce).await?;
+    publish_discovery_config(&client).await?;
+
+    // forward height changes onto the state topic as they arrive, independently of the main
+    // event loop below which is busy handling incoming commands.
+    let state_client = client.clone();
+    let mut height_rx = desk.subscribe_height().await;
+
+    tokio::spawn(async move {
+        while height_rx.changed().await.is_ok() {
+            let height = *height_rx.borrow();
+
+            if let Err(e) = state_client
+                .publish(STATE_TOPIC, QoS::AtLeastOnce, false, height.to_string())
+                .await
+            {
+                log::warn!("failed to publish desk height: {e:?}");
+            }
+        }
+    });
+
+    loop {
+        // rumqttc reconnects internally on the next poll() after a transient error (broker
+        // restart, dropped connection, etc.) - log and keep polling instead of tearing down the
+        // whole service over it.
+        let event = match event_loop.poll().await {
+            Ok(event) => event,
+            Err(e) => {
+                log::warn!("mqtt connection error, reconnecting: {e:?}");
+                continue;
+            }
+        };
+
+        if let Event::Incoming(Packet::Publish(publish)) = event {
+            if publish.topic == COMMAND_TOPIC {
+                let command = String::from_utf8_lossy(&publish.payload).trim().to_string();
+                handle_command(&desk, config, &command).await;
+            }
+        }
+    }
+}
+
+/// Apply a single command received on `COMMAND_TOPIC` to the desk.
+///
+/// # Arguments
+///
+/// * `desk`: The desk to act on.
+/// * `config`: The user's saved presets, consulted for the `sit`/`stand` shorthands.
+/// * `command`: `"sit"`, `"stand"`, `"stop"`, or a target height such as `"0.85"`.
+///
+async fn handle_command(desk: &Desk, config: &Config, command: &str) {
+    let result = match command {
+        "sit" => {
+            let target = config
+                .presets
+                .get("sit")
+                .copied()
+                .unwrap_or(DEFAULT_SIT_HEIGHT);
+
+            desk.move_to_target(target).await
+        }
+        "stand" => {
+            let target = config
+                .presets
+                .get("stand")
+                .copied()
+                .unwrap_or(DEFAULT_STAND_HEIGHT);
+
+            desk.move_to_target(target).await
+        }
+        "stop" => desk.stop().await,
+        target => match target.parse::<f32>() {
+            Ok(target) => desk.move_to_target(target).await,
+            Err(_) => {
+                log::warn!("received unrecognised mqtt command: {command:?}");
+                return;
+            }
+        },
+    };
+
+    if let Err(e) = result {
+        log::warn!("failed to apply mqtt command {command:?}: {e:?}");
+    }
+}
+
+/// Publish a retained Home Assistant MQTT discovery config so the desk appears automatically as
+/// a `number` entity, without any manual `configuration.yaml` setup.
+///
+/// # Arguments
+///
+/// * `client`: The MQTT client to publish the discovery config with.
+///
+/// returns: Result<(), DeskError>
+///
+async fn publish_discovery_config(client: &AsyncClient) -> Result<(), DeskError> {
+    let config = json!({
+        "name": "Idasen Desk",
+        "unique_id": "idasen_desk",
+        "command_topic": COMMAND_TOPIC,
+        "state_topic": STATE_TOPIC,
+        "min": MIN_HEIGHT,
+        "max": MAX_HEIGHT,
+        "step": 0.01,
+        "mode": "slider",
+    });
+
+    client
+        .publish(DISCOVERY_TOPIC, QoS::AtLeastOnce, true, config.to_string())
+        .await?;
+
+    Ok(())
+}